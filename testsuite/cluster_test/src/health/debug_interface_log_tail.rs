@@ -1,117 +1,308 @@
 use crate::{
     cluster::Cluster,
-    health::{Commit, Event, LogTail, ValidatorEvent},
+    health::{
+        event_store::EventStore,
+        liveness_notifier::{LivenessNotifier, NotifierConfig},
+        push_event_collector::{self, PushCollectorConfig},
+        Commit, Event, LogTail, ValidatorEvent,
+    },
     instance::Instance,
     util::unix_timestamp_now,
 };
-use debug_interface::{
-    self,
-    proto::{
-        node_debug_interface::{Event as DebugInterfaceEvent, GetEventsRequest},
-        node_debug_interface_grpc::NodeDebugInterfaceClient,
-    },
-};
-use grpcio::{self, ChannelBuilder, EnvBuilder};
+use debug_interface::{self, async_node_debug_client::AsyncNodeDebugClient};
 use serde_json::{self, value as json};
 use std::{
     env,
-    sync::{atomic::AtomicI64, mpsc, Arc},
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        mpsc, Arc,
+    },
     thread,
     time::Duration,
 };
 
+/// How long a single `get_events` attempt is allowed to take before the worker treats it as a
+/// failure and moves on, so an unreachable node can't wedge its poll loop (or the readiness
+/// signal below) indefinitely.
+const GET_EVENTS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Once the number of events buffered in `LogTail`'s channel crosses this mark, a worker widens
+/// its poll interval to `BACKOFF_POLL_INTERVAL` until the consumer catches up, so it adds events
+/// to an already-backed-up mpsc queue far less often.
+const PENDING_MESSAGES_HIGH_WATER_MARK: i64 = 10_000;
+
+/// Poll interval used while a worker is below the high-water mark.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Poll interval used while a worker is backing off because the consumer is falling behind.
+const BACKOFF_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a configured `LivenessNotifier` re-checks the cluster for instances that have gone
+/// silent for longer than its threshold.
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct DebugPortLogThread {
     instance: Instance,
-    client: NodeDebugInterfaceClient,
+    client: AsyncNodeDebugClient,
     event_sender: mpsc::Sender<ValidatorEvent>,
+    pending_messages: Arc<AtomicI64>,
+    parse_failures: Arc<AtomicU64>,
+    event_store: Option<Arc<EventStore>>,
+    verbose: bool,
 }
 
-impl DebugPortLogThread {
-    pub fn spawn_new(cluster: &Cluster) -> LogTail {
+/// Builds a `LogTail` over one async tail worker per cluster instance, optionally persisting
+/// every parsed event to a `EventStore` for post-mortem replay.
+#[derive(Default)]
+pub struct LogTailBuilder {
+    event_store: Option<Arc<EventStore>>,
+    push_collector: Option<PushCollectorConfig>,
+    notifier: Option<Arc<LivenessNotifier>>,
+}
+
+impl LogTailBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables durable capture of every parsed event into the SQLite database at `path`.
+    pub fn with_event_store(mut self, path: &str) -> rusqlite::Result<Self> {
+        self.event_store = Some(Arc::new(EventStore::new(path)?));
+        Ok(self)
+    }
+
+    /// Switches event ingestion from polling every node's debug port to accepting signed,
+    /// batched pushes on `config.listen_address` instead.
+    pub fn with_push_collector(mut self, config: PushCollectorConfig) -> Self {
+        self.push_collector = Some(config);
+        self
+    }
+
+    /// Alerts through `config` when an instance produces no commit for longer than `threshold`.
+    pub fn with_liveness_notifier(mut self, config: NotifierConfig, threshold: Duration) -> Self {
+        self.notifier = Some(Arc::new(LivenessNotifier::new(config, threshold)));
+        self
+    }
+
+    pub fn spawn(self, cluster: &Cluster) -> LogTail {
         let (event_sender, event_receiver) = mpsc::channel();
-        let env = Arc::new(EnvBuilder::new().name_prefix("grpc-log-tail-").build());
-        for instance in cluster.instances() {
-            let ch =
-                ChannelBuilder::new(env.clone()).connect(&format!("{}:{}", instance.ip(), 6191));
-            let client = NodeDebugInterfaceClient::new(ch);
-            let debug_port_log_thread = DebugPortLogThread {
-                instance: instance.clone(),
-                client,
-                event_sender: event_sender.clone(),
-            };
-            thread::Builder::new()
-                .name(format!("log-tail-{}", instance.short_hash()))
-                .spawn(move || debug_port_log_thread.run())
-                .expect("Failed to spawn log tail thread");
+        let pending_messages = Arc::new(AtomicI64::new(0));
+        let parse_failures = Arc::new(AtomicU64::new(0));
+        let mut started_receivers = vec![];
+        if let Some(notifier) = &self.notifier {
+            notifier.seed(cluster.instances().iter().map(|i| i.short_hash().clone()));
+            let notifier = notifier.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(LIVENESS_CHECK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    notifier.check_for_stuck_instances();
+                }
+            });
+        }
+        if let Some(push_collector) = self.push_collector {
+            push_event_collector::spawn(
+                push_collector,
+                event_sender.clone(),
+                pending_messages.clone(),
+                self.event_store.clone(),
+            );
+        } else {
+            let verbose = env::var("VERBOSE").is_ok();
+            for instance in cluster.instances() {
+                let client = AsyncNodeDebugClient::new(instance.ip(), 6191);
+                let debug_port_log_thread = DebugPortLogThread {
+                    instance: instance.clone(),
+                    client,
+                    event_sender: event_sender.clone(),
+                    pending_messages: pending_messages.clone(),
+                    parse_failures: parse_failures.clone(),
+                    event_store: self.event_store.clone(),
+                    verbose,
+                };
+                let (started_tx, started_rx) = mpsc::channel::<()>();
+                started_receivers.push(started_rx);
+                // Each worker gets its own single-threaded tokio runtime on a dedicated OS
+                // thread, exactly like the old one-thread-per-instance design, so the poll workers
+                // don't require the caller to already be inside a (multi-thread) tokio runtime.
+                // The liveness notifier loop and the push collector are started with
+                // `tokio::spawn`, so configuring either does require one.
+                thread::Builder::new()
+                    .name(format!("log-tail-{}", instance.short_hash()))
+                    .spawn(move || {
+                        let runtime = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                            .expect("Failed to build log tail worker runtime");
+                        runtime.block_on(debug_port_log_thread.run(started_tx));
+                    })
+                    .expect("Failed to spawn log tail thread");
+            }
+        }
+        // Block the caller until every instance's tail has made its first poll attempt, rather
+        // than racing a health check against lazily-spawned workers. This is a plain blocking
+        // recv on a std channel, so it's safe to call from any thread regardless of whether (or
+        // what kind of) tokio runtime, if any, the caller happens to be running under.
+        for started_rx in started_receivers {
+            let _ignore = started_rx.recv();
         }
         LogTail {
             event_receiver,
-            pending_messages: Arc::new(AtomicI64::new(0)),
+            pending_messages,
+            parse_failures,
+            notifier: self.notifier,
         }
     }
 }
 
 impl DebugPortLogThread {
-    pub fn run(self) {
-        let print_failures = env::var("VERBOSE").is_ok();
+    pub fn spawn_new(cluster: &Cluster) -> LogTail {
+        LogTailBuilder::new().spawn(cluster)
+    }
+}
+
+impl DebugPortLogThread {
+    pub async fn run(self, started: mpsc::Sender<()>) {
+        let print_failures = self.verbose;
+        let mut started = Some(started);
         loop {
-            let opts = grpcio::CallOption::default().timeout(Duration::from_secs(5));
-            match self.client.get_events_opt(&GetEventsRequest::new(), opts) {
+            let outcome: Result<_, String> =
+                match tokio::time::timeout(GET_EVENTS_TIMEOUT, self.client.get_events()).await {
+                    Ok(Ok(events)) => Ok(events),
+                    Ok(Err(e)) => Err(format!("{:?}", e)),
+                    Err(_elapsed) => {
+                        Err(format!("get_events timed out after {:?}", GET_EVENTS_TIMEOUT))
+                    }
+                };
+            // Signal readiness after the first poll attempt completes (success or failure), not
+            // the first success, so a node that never comes up degrades the caller's wait into a
+            // bounded one instead of hanging `spawn` forever.
+            if let Some(tx) = started.take() {
+                let _ignore = tx.send(());
+            }
+            match outcome {
                 Err(e) => {
                     if print_failures {
-                        println!("Failed to get events from {}: {:?}", self.instance, e);
+                        println!("Failed to get events from {}: {}", self.instance, e);
                     }
-                    thread::sleep(Duration::from_secs(1));
+                    tokio::time::sleep(Duration::from_secs(1)).await;
                 }
-                Ok(resp) => {
-                    for event in resp.events.into_iter() {
-                        if let Some(e) = self.parse_event(event) {
-                            let _ignore = self.event_sender.send(e);
+                Ok(events) => {
+                    for event in events.into_iter() {
+                        match self.parse_event(event) {
+                            Ok(Some(e)) => {
+                                if let Some(event_store) = &self.event_store {
+                                    if let Err(err) = event_store.store(&e) {
+                                        if print_failures {
+                                            println!(
+                                                "Failed to persist event from {}: {}",
+                                                self.instance, err
+                                            );
+                                        }
+                                    }
+                                }
+                                if self.event_sender.send(e).is_ok() {
+                                    self.pending_messages.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                self.parse_failures.fetch_add(1, Ordering::Relaxed);
+                                if print_failures {
+                                    println!(
+                                        "Failed to parse event from {}: {}",
+                                        self.instance, e
+                                    );
+                                }
+                            }
                         }
                     }
-                    thread::sleep(Duration::from_millis(200));
+                    let poll_interval =
+                        if self.pending_messages.load(Ordering::Relaxed)
+                            > PENDING_MESSAGES_HIGH_WATER_MARK
+                        {
+                            BACKOFF_POLL_INTERVAL
+                        } else {
+                            POLL_INTERVAL
+                        };
+                    tokio::time::sleep(poll_interval).await;
                 }
             }
         }
     }
 
-    fn parse_event(&self, event: DebugInterfaceEvent) -> Option<ValidatorEvent> {
-        let json: json::Value =
-            serde_json::from_str(&event.json).expect("Failed to parse json from debug interface");
+    /// Parses a single debug-interface event. Returns `Err` when the payload isn't valid JSON
+    /// (whatever the event name) or when the payload for a recognized event name doesn't match
+    /// the shape we expect, and `Ok(None)` for a valid-JSON event whose name we don't recognize
+    /// (not a failure, just not actionable), so a malformed event from one node degrades into a
+    /// counted, logged skip instead of tearing down its tail worker.
+    fn parse_event(
+        &self,
+        event: debug_interface::proto::node_debug_interface::Event,
+    ) -> Result<Option<ValidatorEvent>, String> {
+        let json: json::Value = serde_json::from_str(&event.json)
+            .map_err(|e| format!("Failed to parse json from debug interface: {}", e))?;
 
-        let e = if event.name == "committed" {
-            Self::parse_commit(json)
-        } else {
-            println!("Unknown event: {} from {}", event.name, self.instance);
-            return None;
+        let e = match event.name.as_str() {
+            "committed" => Self::parse_commit(json)?,
+            "proposal" => Self::parse_proposal(json)?,
+            "timeout" => Self::parse_timeout(json)?,
+            "new_epoch" => Self::parse_new_epoch(json)?,
+            _ => {
+                if self.verbose {
+                    println!("Unknown event: {} from {}", event.name, self.instance);
+                }
+                return Ok(None);
+            }
         };
-        Some(ValidatorEvent {
+        Ok(Some(ValidatorEvent {
             validator: self.instance.short_hash().clone(),
             timestamp: Duration::from_millis(event.get_timestamp() as u64),
             received_timestamp: unix_timestamp_now(),
             event: e,
+        }))
+    }
+
+    fn get_str(json: &json::Value, field: &str) -> Result<String, String> {
+        json.get(field)
+            .ok_or_else(|| format!("No {} in event", field))?
+            .as_str()
+            .ok_or_else(|| format!("{} is not a string", field))
+            .map(str::to_string)
+    }
+
+    fn get_u64(json: &json::Value, field: &str) -> Result<u64, String> {
+        json.get(field)
+            .ok_or_else(|| format!("No {} in event", field))?
+            .as_u64()
+            .ok_or_else(|| format!("{} is not a u64", field))
+    }
+
+    fn parse_commit(json: json::Value) -> Result<Event, String> {
+        Ok(Event::Commit(Commit {
+            commit: Self::get_str(&json, "block_id")?,
+            round: Self::get_u64(&json, "round")?,
+            parent: Self::get_str(&json, "parent_id")?,
+        }))
+    }
+
+    fn parse_proposal(json: json::Value) -> Result<Event, String> {
+        Ok(Event::Proposal {
+            round: Self::get_u64(&json, "round")?,
+            block_id: Self::get_str(&json, "block_id")?,
+            proposer: Self::get_str(&json, "proposer")?,
+        })
+    }
+
+    fn parse_timeout(json: json::Value) -> Result<Event, String> {
+        Ok(Event::Timeout {
+            round: Self::get_u64(&json, "round")?,
         })
     }
 
-    fn parse_commit(json: json::Value) -> Event {
-        Event::Commit(Commit {
-            commit: json
-                .get("block_id")
-                .expect("No block_id in commit event")
-                .as_str()
-                .expect("block_id is not string")
-                .to_string(),
-            round: json
-                .get("round")
-                .expect("No round in commit event")
-                .as_u64()
-                .expect("round is not u64"),
-            parent: json
-                .get("parent_id")
-                .expect("No parent_id in commit event")
-                .as_str()
-                .expect("parent_id is not string")
-                .to_string(),
+    fn parse_new_epoch(json: json::Value) -> Result<Event, String> {
+        Ok(Event::NewEpoch {
+            epoch: Self::get_u64(&json, "epoch")?,
         })
     }
 }
@@ -0,0 +1,132 @@
+use crate::health::{Event, ValidatorEvent};
+use rusqlite::{params, Connection};
+use std::{
+    sync::Mutex,
+    time::{Duration, UNIX_EPOCH},
+};
+
+/// Persists every parsed `ValidatorEvent` to a SQLite database so a failed test run's event
+/// history can be inspected offline, instead of only existing transiently in the in-memory
+/// mpsc channel.
+pub struct EventStore {
+    conn: Mutex<Connection>,
+}
+
+impl EventStore {
+    pub fn new(path: &str) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS events (
+                id                INTEGER PRIMARY KEY AUTOINCREMENT,
+                validator         TEXT NOT NULL,
+                kind              TEXT NOT NULL,
+                round             INTEGER,
+                block_id          TEXT,
+                parent_id         TEXT,
+                node_timestamp_ms INTEGER NOT NULL,
+                received_at_ms    INTEGER NOT NULL
+            )",
+            params![],
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn store(&self, event: &ValidatorEvent) -> rusqlite::Result<()> {
+        let (kind, round, block_id, parent_id) = match &event.event {
+            Event::Commit(commit) => (
+                "commit",
+                Some(commit.round),
+                Some(commit.commit.clone()),
+                Some(commit.parent.clone()),
+            ),
+            Event::Proposal {
+                round,
+                block_id,
+                proposer: _,
+            } => ("proposal", Some(*round), Some(block_id.clone()), None),
+            Event::Timeout { round } => ("timeout", Some(*round), None, None),
+            Event::NewEpoch { epoch } => ("new_epoch", Some(*epoch), None, None),
+        };
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO events
+                (validator, kind, round, block_id, parent_id, node_timestamp_ms, received_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                event.validator,
+                kind,
+                round.map(|r| r as i64),
+                block_id,
+                parent_id,
+                event.timestamp.as_millis() as i64,
+                event.received_timestamp.as_millis() as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Replays all stored events for a given instance, ordered by when the node emitted them.
+    pub fn events_for_instance(&self, validator: &str) -> rusqlite::Result<Vec<StoredEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT validator, kind, round, block_id, parent_id, node_timestamp_ms, received_at_ms
+             FROM events WHERE validator = ?1 ORDER BY node_timestamp_ms ASC",
+        )?;
+        let rows = stmt.query_map(params![validator], Self::row_to_stored_event)?;
+        rows.collect()
+    }
+
+    /// Replays all stored events whose round falls within `[start, end]`, across every instance.
+    /// `new_epoch` events store their epoch number in the round column, so they are matched on
+    /// their epoch.
+    pub fn events_for_round_range(&self, start: u64, end: u64) -> rusqlite::Result<Vec<StoredEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT validator, kind, round, block_id, parent_id, node_timestamp_ms, received_at_ms
+             FROM events WHERE round BETWEEN ?1 AND ?2 ORDER BY node_timestamp_ms ASC",
+        )?;
+        let rows = stmt.query_map(params![start as i64, end as i64], Self::row_to_stored_event)?;
+        rows.collect()
+    }
+
+    /// Computes the distribution of commit latencies (received time minus node-reported time)
+    /// across every commit event recorded for the cluster.
+    pub fn commit_latencies(&self) -> rusqlite::Result<Vec<Duration>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT node_timestamp_ms, received_at_ms FROM events WHERE kind = 'commit'",
+        )?;
+        let rows = stmt.query_map(params![], |row| {
+            let node_timestamp_ms: i64 = row.get(0)?;
+            let received_at_ms: i64 = row.get(1)?;
+            Ok(received_at_ms.saturating_sub(node_timestamp_ms))
+        })?;
+        rows.map(|r| r.map(|millis| Duration::from_millis(millis.max(0) as u64)))
+            .collect()
+    }
+
+    fn row_to_stored_event(row: &rusqlite::Row) -> rusqlite::Result<StoredEvent> {
+        Ok(StoredEvent {
+            validator: row.get(0)?,
+            kind: row.get(1)?,
+            round: row.get::<_, Option<i64>>(2)?.map(|r| r as u64),
+            block_id: row.get(3)?,
+            parent_id: row.get(4)?,
+            node_timestamp: UNIX_EPOCH + Duration::from_millis(row.get::<_, i64>(5)? as u64),
+            received_timestamp: UNIX_EPOCH + Duration::from_millis(row.get::<_, i64>(6)? as u64),
+        })
+    }
+}
+
+/// A single event as replayed from the `EventStore`.
+#[derive(Debug, Clone)]
+pub struct StoredEvent {
+    pub validator: String,
+    pub kind: String,
+    pub round: Option<u64>,
+    pub block_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub node_timestamp: std::time::SystemTime,
+    pub received_timestamp: std::time::SystemTime,
+}
@@ -0,0 +1,139 @@
+use crate::health::{Event, ValidatorEvent};
+use reqwest;
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Where a `LivenessNotifier` sends its stuck/resolved alerts.
+pub enum NotifierConfig {
+    /// POST a JSON payload describing the stuck (or recovered) instance to `url`.
+    Webhook { url: String },
+    /// Append a line describing the event to the file at `path`.
+    LogFile { path: String },
+}
+
+/// Watches the `ValidatorEvent` stream for instances that stop committing and fires a
+/// notification through the configured `NotifierConfig` once an instance has been silent for
+/// longer than `threshold`. Debounced so a recovered node only produces a single "resolved"
+/// notification instead of one per subsequent commit.
+pub struct LivenessNotifier {
+    config: NotifierConfig,
+    threshold: Duration,
+    last_commit: Mutex<HashMap<String, Instant>>,
+    stuck: Mutex<HashSet<String>>,
+}
+
+impl LivenessNotifier {
+    pub fn new(config: NotifierConfig, threshold: Duration) -> Self {
+        Self {
+            config,
+            threshold,
+            last_commit: Mutex::new(HashMap::new()),
+            stuck: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Registers instances as "just committed" at startup so a validator that crashes before
+    /// ever producing a commit is still tracked and gets flagged as stuck once `threshold`
+    /// elapses, instead of being invisible because it never appeared in `last_commit`.
+    pub fn seed(&self, instance_hashes: impl IntoIterator<Item = String>) {
+        let now = Instant::now();
+        let mut last_commit = self.last_commit.lock().unwrap();
+        for instance_hash in instance_hashes {
+            last_commit.entry(instance_hash).or_insert(now);
+        }
+    }
+
+    /// Feeds a single validator event into the notifier's bookkeeping. Should be called by the
+    /// `LogTail` consumer for every event it drains.
+    pub fn observe(&self, event: &ValidatorEvent) {
+        if let Event::Commit(_) = &event.event {
+            let instance_hash = event.validator.clone();
+            self.last_commit
+                .lock()
+                .unwrap()
+                .insert(instance_hash.clone(), Instant::now());
+            if self.stuck.lock().unwrap().remove(&instance_hash) {
+                self.notify_resolved(&instance_hash);
+            }
+        }
+    }
+
+    /// Checks every instance with a known last-commit time against `threshold`, firing a
+    /// notification for any instance that just crossed into silence. Intended to be called on a
+    /// timer by the owner of the notifier.
+    pub fn check_for_stuck_instances(&self) {
+        let now = Instant::now();
+        let silent: Vec<(String, Duration)> = self
+            .last_commit
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(instance_hash, last_commit)| {
+                let silence = now.duration_since(*last_commit);
+                if silence > self.threshold {
+                    Some((instance_hash.clone(), silence))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for (instance_hash, silence) in silent {
+            if self.stuck.lock().unwrap().insert(instance_hash.clone()) {
+                self.notify_stuck(&instance_hash, silence);
+            }
+        }
+    }
+
+    fn notify_stuck(&self, instance_hash: &str, silence: Duration) {
+        self.dispatch(json!({
+            "instance": instance_hash,
+            "status": "stuck",
+            "silence_secs": silence.as_secs(),
+        }));
+    }
+
+    fn notify_resolved(&self, instance_hash: &str) {
+        self.dispatch(json!({
+            "instance": instance_hash,
+            "status": "resolved",
+        }));
+    }
+
+    /// Performs the actual webhook POST / log-file write on a dedicated OS thread. Both are
+    /// blocking I/O (and `reqwest::blocking` stands up its own little runtime under the hood),
+    /// so this must never run on a tokio worker thread driving `check_for_stuck_instances` or
+    /// `observe` — doing so would either stall the runtime or, for an async runtime already
+    /// entered on that thread, panic outright.
+    fn dispatch(&self, payload: serde_json::Value) {
+        match &self.config {
+            NotifierConfig::Webhook { url } => {
+                let url = url.clone();
+                thread::spawn(move || {
+                    if let Err(e) = reqwest::blocking::Client::new().post(&url).json(&payload).send() {
+                        println!("Failed to send liveness notification to {}: {:?}", url, e);
+                    }
+                });
+            }
+            NotifierConfig::LogFile { path } => {
+                let path = path.clone();
+                thread::spawn(move || {
+                    use std::io::Write;
+                    let line = format!("{}\n", payload);
+                    match std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+                        Ok(mut file) => {
+                            if let Err(e) = file.write_all(line.as_bytes()) {
+                                println!("Failed to write liveness notification to {}: {:?}", path, e);
+                            }
+                        }
+                        Err(e) => println!("Failed to open liveness notification log {}: {:?}", path, e),
+                    }
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+pub mod debug_interface_log_tail;
+pub mod event_store;
+pub mod liveness_notifier;
+pub mod push_event_collector;
+
+use liveness_notifier::LivenessNotifier;
+use std::{
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub commit: String,
+    pub round: u64,
+    pub parent: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Commit(Commit),
+    Proposal {
+        round: u64,
+        block_id: String,
+        proposer: String,
+    },
+    Timeout {
+        round: u64,
+    },
+    NewEpoch {
+        epoch: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatorEvent {
+    pub validator: String,
+    pub timestamp: Duration,
+    pub received_timestamp: Duration,
+    pub event: Event,
+}
+
+/// Consumer-facing handle onto the event stream a `LogTailBuilder` assembles. Draining through
+/// `recv`/`try_recv` is how `pending_messages` actually comes back down after a tail worker
+/// increments it, so a slow-but-catching-up consumer isn't mistaken for a stuck one.
+pub struct LogTail {
+    pub(crate) event_receiver: mpsc::Receiver<ValidatorEvent>,
+    pub(crate) pending_messages: Arc<AtomicI64>,
+    pub(crate) parse_failures: Arc<AtomicU64>,
+    pub(crate) notifier: Option<Arc<LivenessNotifier>>,
+}
+
+impl LogTail {
+    /// Blocks until the next event is available.
+    pub fn recv(&self) -> Result<ValidatorEvent, mpsc::RecvError> {
+        let event = self.event_receiver.recv()?;
+        self.pending_messages.fetch_sub(1, Ordering::Relaxed);
+        if let Some(notifier) = &self.notifier {
+            notifier.observe(&event);
+        }
+        Ok(event)
+    }
+
+    /// Non-blocking drain of the next available event, if any.
+    pub fn try_recv(&self) -> Result<ValidatorEvent, mpsc::TryRecvError> {
+        let event = self.event_receiver.try_recv()?;
+        self.pending_messages.fetch_sub(1, Ordering::Relaxed);
+        if let Some(notifier) = &self.notifier {
+            notifier.observe(&event);
+        }
+        Ok(event)
+    }
+
+    pub fn pending_messages(&self) -> i64 {
+        self.pending_messages.load(Ordering::Relaxed)
+    }
+
+    pub fn parse_failures(&self) -> u64 {
+        self.parse_failures.load(Ordering::Relaxed)
+    }
+}
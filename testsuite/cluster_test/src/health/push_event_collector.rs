@@ -0,0 +1,135 @@
+use crate::health::{event_store::EventStore, Commit, Event, ValidatorEvent};
+use hmac::{Hmac, Mac, NewMac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::Duration,
+};
+use warp::{http::StatusCode, Filter};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Caps the size of a single pushed batch so a misbehaving or malicious node can't force the
+/// collector to buffer an unbounded body before the signature is even checked.
+const MAX_BATCH_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Configuration for the push-based event collector: where it listens and the secret nodes sign
+/// their batches with.
+pub struct PushCollectorConfig {
+    pub listen_address: SocketAddr,
+    pub shared_secret: Vec<u8>,
+}
+
+/// A single event as pushed by a node, mirroring the shape polled from the debug interface.
+#[derive(Deserialize)]
+struct PushedEvent {
+    validator: String,
+    name: String,
+    round: u64,
+    block_id: Option<String>,
+    parent_id: Option<String>,
+    proposer: Option<String>,
+    timestamp_ms: u64,
+}
+
+/// Spawns an HTTP collector that accepts batched, HMAC-signed event pushes from nodes as an
+/// alternative to polling every node's debug port. Valid batches are fed into the same
+/// `event_sender` channel (and `pending_messages` counter) that the poll-based tail workers use,
+/// and persisted to `event_store` if one is configured, so existing health checks and replay
+/// tooling work unchanged regardless of which ingestion mode produced an event.
+///
+/// Binds synchronously before returning, so a caller that starts telling nodes to push as soon as
+/// this returns won't race an asynchronously-bound listener.
+pub fn spawn(
+    config: PushCollectorConfig,
+    event_sender: mpsc::Sender<ValidatorEvent>,
+    pending_messages: Arc<AtomicI64>,
+    event_store: Option<Arc<EventStore>>,
+) {
+    let shared_secret = config.shared_secret;
+    // `warp::serve` requires its filter to be `Send + Sync + 'static` since it's shared across
+    // concurrently-handled requests, but `mpsc::Sender` is not `Sync` — wrap it so the one
+    // underlying sender can be safely reached from any request task.
+    let event_sender = Arc::new(Mutex::new(event_sender));
+    let route = warp::path("events")
+        .and(warp::post())
+        .and(warp::header::<String>("x-signature"))
+        .and(warp::body::content_length_limit(MAX_BATCH_BYTES))
+        .and(warp::body::bytes())
+        .map(move |signature: String, body: bytes::Bytes| {
+            if !verify_signature(&shared_secret, &body, &signature) {
+                return warp::reply::with_status(
+                    "signature mismatch".to_string(),
+                    StatusCode::UNAUTHORIZED,
+                );
+            }
+            match serde_json::from_slice::<Vec<PushedEvent>>(&body) {
+                Ok(events) => {
+                    for pushed in events {
+                        if let Some(event) = to_validator_event(pushed) {
+                            if let Some(event_store) = &event_store {
+                                if let Err(err) = event_store.store(&event) {
+                                    println!("Failed to persist pushed event: {}", err);
+                                }
+                            }
+                            if event_sender.lock().unwrap().send(event).is_ok() {
+                                pending_messages.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                    warp::reply::with_status("ok".to_string(), StatusCode::OK)
+                }
+                Err(e) => warp::reply::with_status(
+                    format!("malformed batch: {}", e),
+                    StatusCode::BAD_REQUEST,
+                ),
+            }
+        });
+    let (_bound_address, server) = warp::serve(route).bind_ephemeral(config.listen_address);
+    tokio::spawn(server);
+}
+
+/// Verifies that `signature` (hex-encoded) is the HMAC-SHA256 of `body` under `shared_secret`,
+/// rejecting on mismatch so a tampered or unauthenticated push is dropped before it ever reaches
+/// the event channel.
+fn verify_signature(shared_secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let expected = match hex::decode(signature) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+    let mut mac = match HmacSha256::new_from_slice(shared_secret) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+    mac.update(body);
+    mac.verify(&expected).is_ok()
+}
+
+fn to_validator_event(pushed: PushedEvent) -> Option<ValidatorEvent> {
+    let event = match pushed.name.as_str() {
+        "committed" => Event::Commit(Commit {
+            commit: pushed.block_id?,
+            round: pushed.round,
+            parent: pushed.parent_id?,
+        }),
+        "proposal" => Event::Proposal {
+            round: pushed.round,
+            block_id: pushed.block_id?,
+            proposer: pushed.proposer?,
+        },
+        "timeout" => Event::Timeout { round: pushed.round },
+        "new_epoch" => Event::NewEpoch { epoch: pushed.round },
+        _ => return None,
+    };
+    Some(ValidatorEvent {
+        validator: pushed.validator,
+        timestamp: Duration::from_millis(pushed.timestamp_ms),
+        received_timestamp: crate::util::unix_timestamp_now(),
+        event,
+    })
+}